@@ -0,0 +1,388 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, FnArg, ForeignItemFn, ItemConst, ItemFn, LitStr, Pat, Result};
+
+use super::fn_options::HotFunctionOptions;
+use crate::sig_hash::{hash_signature, sig_hash_symbol_name};
+
+/// Generates the hot-reloadable wrapper function for a single exported function,
+/// e.g. turns
+/// ```ignore
+/// pub fn do_stuff(arg: &str) -> u32;
+/// ```
+/// into a real `fn do_stuff` that resolves `do_stuff` from the currently loaded
+/// library and calls it. `options` carries anything configured via a
+/// `#[hot_function(...)]` attribute on this function.
+///
+/// The returned `bool` says whether the exported symbol is *required*: `false`
+/// for `optional`/`on_missing` functions, since those are expected to tolerate a
+/// missing symbol, so callers collecting symbols for `verify_symbols()` should
+/// skip them rather than reporting them as missing on every legitimate use.
+pub(crate) fn gen_hot_module_function_for(
+    f: ForeignItemFn,
+    span: Span,
+    options: HotFunctionOptions,
+) -> Result<(ItemConst, ItemFn, String, bool)> {
+    let ForeignItemFn { vis, sig, .. } = &f;
+
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            format!(
+                "`fn {}` cannot be generic: hot-reloaded functions are called through a \
+                 dynamically resolved symbol, so their signature must be fully concrete",
+                sig.ident
+            ),
+        ));
+    }
+
+    if options.optional && options.on_missing.is_some() {
+        return Err(syn::Error::new(
+            sig.ident.span(),
+            "`optional` and `on_missing` are mutually exclusive: `optional` reports a \
+             missing symbol to the caller, `on_missing` calls a fallback instead",
+        ));
+    }
+
+    let fn_name = &sig.ident;
+    let fn_name_str = fn_name.to_string();
+    let exported_name = options
+        .symbol
+        .as_ref()
+        .map(|lit| lit.value())
+        .unwrap_or_else(|| fn_name_str.clone());
+    let symbol_name = LitStr::new(&format!("{exported_name}\0"), span);
+
+    let sig_hash = hash_signature(sig);
+    let sig_hash_const = format_ident!("__HOT_SIG_HASH_{}", fn_name_str.to_uppercase());
+    let sig_hash_symbol =
+        LitStr::new(&format!("{}\0", sig_hash_symbol_name(&exported_name)), span);
+
+    let inputs = &sig.inputs;
+    let arg_names: Vec<_> = sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                Pat::Ident(pat_ident) => Some(pat_ident.ident.clone()),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let arg_types: Vec<_> = sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => Some(pat_type.ty.as_ref()),
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    let output = &sig.output;
+    let fn_ptr_type = quote! { unsafe extern "C" fn(#(#arg_types),*) #output };
+
+    // If the reloaded library exports a signature hash for this function, check
+    // it against what we generated against (without panicking yet); otherwise
+    // `sig_mismatch` is `false` so libs built before this feature existed keep
+    // working. What a mismatch *does* then depends on the wrapper below: a
+    // plain wrapper has no other way to report it and panics, but `optional`/
+    // `on_missing` opted out of panicking on a bad reload, so a mismatch is
+    // handled the same way a missing symbol would be.
+    let sig_hash_check = quote! {
+        let sig_mismatch = match lib
+            .get_symbol::<unsafe extern "C" fn() -> u64>(#sig_hash_symbol.as_bytes())
+        {
+            Ok(get_sig_hash) => get_sig_hash() != #sig_hash_const,
+            Err(_) => false,
+        };
+    };
+
+    let body = if options.optional {
+        quote! {
+            unsafe {
+                #sig_hash_check
+
+                let f: Option<libloading::Symbol<#fn_ptr_type>> = if sig_mismatch {
+                    None
+                } else {
+                    lib.get_symbol(#symbol_name.as_bytes()).ok()
+                };
+
+                f.map(|f| f(#(#arg_names),*))
+            }
+        }
+    } else if let Some(on_missing) = &options.on_missing {
+        let fallback = crate::util::parse_ident_literal(on_missing)?;
+        quote! {
+            unsafe {
+                #sig_hash_check
+
+                if sig_mismatch {
+                    #fallback(#(#arg_names),*)
+                } else {
+                    match lib.get_symbol::<#fn_ptr_type>(#symbol_name.as_bytes()) {
+                        Ok(f) => f(#(#arg_names),*),
+                        Err(_) => #fallback(#(#arg_names),*),
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {
+            unsafe {
+                #sig_hash_check
+
+                if sig_mismatch {
+                    panic!(
+                        "signature mismatch for `fn {}`: the reloaded library was built \
+                         against a different signature than this hot module expects",
+                        #fn_name_str
+                    );
+                }
+
+                let f: libloading::Symbol<#fn_ptr_type> = lib
+                    .get_symbol(#symbol_name.as_bytes())
+                    .unwrap_or_else(|err| {
+                        panic!("cannot find function `{}` in lib: {}", #fn_name_str, err)
+                    });
+
+                f(#(#arg_names),*)
+            }
+        }
+    };
+
+    let wrapped_output = if options.optional {
+        let ret_ty = match output {
+            syn::ReturnType::Default => quote! { () },
+            syn::ReturnType::Type(_, ty) => quote! { #ty },
+        };
+        quote! { -> Option<#ret_ty> }
+    } else {
+        quote! { #output }
+    };
+
+    let sig_hash_const_tokens = quote! {
+        #[allow(non_upper_case_globals)]
+        const #sig_hash_const: u64 = #sig_hash;
+    };
+
+    let fn_tokens = quote! {
+        #vis fn #fn_name(#inputs) #wrapped_output {
+            let lib = __hot_reload::lib();
+            #body
+        }
+    };
+
+    let is_required = !options.optional && options.on_missing.is_none();
+
+    Ok((
+        syn::parse2(sig_hash_const_tokens)?,
+        syn::parse2(fn_tokens)?,
+        exported_name,
+        is_required,
+    ))
+}
+
+/// Generates the `LibReloader` plumbing (`__hot_reload` module, `subscribe`, ...)
+/// shared by every generated wrapper function in this `#[hot_module]`.
+pub(crate) fn generate_lib_loader_items(
+    lib_dir: &LitStr,
+    lib_name: &LitStr,
+    _span: Span,
+) -> Result<TokenStream> {
+    Ok(quote! {
+        #[doc(hidden)]
+        mod __hot_reload {
+            use std::sync::{Mutex, OnceLock};
+
+            static LIB_LOADER: OnceLock<Mutex<hot_lib_reloader::LibReloader>> = OnceLock::new();
+
+            pub(super) fn lib() -> std::sync::MutexGuard<'static, hot_lib_reloader::LibReloader> {
+                LIB_LOADER
+                    .get_or_init(|| {
+                        Mutex::new(
+                            hot_lib_reloader::LibReloader::new(#lib_dir, #lib_name, None, None)
+                                .expect("failed to initialize library loader"),
+                        )
+                    })
+                    .lock()
+                    .expect("lib reloader mutex poisoned")
+            }
+        }
+
+        /// Subscribes to notifications about reloads of this hot module's library.
+        #[allow(dead_code)]
+        pub fn subscribe() -> hot_lib_reloader::LibReloadObserver {
+            __hot_reload::lib().subscribe()
+        }
+    })
+}
+
+/// Generates `verify_symbols()` plus its error type, resolving every symbol this
+/// hot module expects (one entry per function in `items` whose symbol is required;
+/// `optional`/`on_missing` functions are not checked) against the currently
+/// loaded library so a bad rebuild can be caught right after a reload, instead of
+/// only at the first call site that happens to hit the missing symbol.
+pub(crate) fn generate_verify_symbols_items(expected_symbols: &[String]) -> Result<TokenStream> {
+    let symbol_lits: Vec<_> = expected_symbols
+        .iter()
+        .map(|name| LitStr::new(&format!("{name}\0"), Span::call_site()))
+        .collect();
+
+    Ok(quote! {
+        /// Enumerates every symbol [`verify_symbols`] expected to find in the
+        /// loaded library but couldn't.
+        #[derive(Debug)]
+        pub struct MissingSymbolsError {
+            pub missing_symbols: Vec<String>,
+        }
+
+        impl std::fmt::Display for MissingSymbolsError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(
+                    f,
+                    "missing symbols in reloaded library: {}",
+                    self.missing_symbols.join(", ")
+                )
+            }
+        }
+
+        impl std::error::Error for MissingSymbolsError {}
+
+        /// Resolves every symbol this hot module expects against the currently
+        /// loaded library, returning a [`MissingSymbolsError`] enumerating any
+        /// that are missing. Call this right after a reload notification to catch
+        /// a bad rebuild before any wrapper function dispatches into it.
+        pub fn verify_symbols() -> std::result::Result<(), MissingSymbolsError> {
+            let lib = __hot_reload::lib();
+            let expected: &[&str] = &[ #(#symbol_lits),* ];
+
+            let missing_symbols: Vec<String> = expected
+                .iter()
+                .filter(|name| unsafe { lib.get_symbol::<*const ()>(name.as_bytes()).is_err() })
+                .map(|name| name.trim_end_matches('\0').to_string())
+                .collect();
+
+            if missing_symbols.is_empty() {
+                Ok(())
+            } else {
+                Err(MissingSymbolsError { missing_symbols })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn generates_a_const_and_a_fn_item_for_a_plain_function() {
+        let f: ForeignItemFn = parse_quote!(pub fn do_stuff(arg: &str) -> u32;);
+        let (sig_const, f, symbol, is_required) =
+            gen_hot_module_function_for(f, Span::call_site(), HotFunctionOptions::default())
+                .expect("a plain function should generate without error");
+
+        assert_eq!(symbol, "do_stuff");
+        assert_eq!(f.sig.ident, "do_stuff");
+        assert!(matches!(sig_const.ty.as_ref(), syn::Type::Path(_)));
+        assert!(is_required);
+    }
+
+    #[test]
+    fn optional_wraps_the_return_type_in_option() {
+        let f: ForeignItemFn = parse_quote!(pub fn do_stuff(arg: &str) -> u32;);
+        let options = HotFunctionOptions {
+            optional: true,
+            ..Default::default()
+        };
+        let (_, f, _, is_required) = gen_hot_module_function_for(f, Span::call_site(), options)
+            .expect("an optional function should generate without error");
+
+        let syn::ReturnType::Type(_, ty) = &f.sig.output else {
+            panic!("expected an explicit return type");
+        };
+        assert_eq!(quote::quote!(#ty).to_string(), quote::quote!(Option<u32>).to_string());
+        assert!(!is_required, "an optional function's symbol shouldn't be required");
+    }
+
+    #[test]
+    fn symbol_option_renames_the_exported_symbol() {
+        let f: ForeignItemFn = parse_quote!(pub fn do_stuff(arg: &str) -> u32;);
+        let options = HotFunctionOptions {
+            symbol: Some(parse_quote!("do_stuff_v2")),
+            ..Default::default()
+        };
+        let (_, _, symbol, _) = gen_hot_module_function_for(f, Span::call_site(), options)
+            .expect("a renamed function should generate without error");
+
+        assert_eq!(symbol, "do_stuff_v2");
+    }
+
+    #[test]
+    fn on_missing_function_s_symbol_is_not_required() {
+        let f: ForeignItemFn = parse_quote!(pub fn do_stuff(arg: &str) -> u32;);
+        let options = HotFunctionOptions {
+            on_missing: Some(parse_quote!("do_stuff_fallback")),
+            ..Default::default()
+        };
+        let (_, _, _, is_required) = gen_hot_module_function_for(f, Span::call_site(), options)
+            .expect("an on_missing function should generate without error");
+
+        assert!(!is_required);
+    }
+
+    #[test]
+    fn on_missing_that_is_not_a_valid_identifier_is_a_syn_error_not_a_panic() {
+        let f: ForeignItemFn = parse_quote!(pub fn do_stuff(arg: &str) -> u32;);
+        let options = HotFunctionOptions {
+            on_missing: Some(parse_quote!("not an ident")),
+            ..Default::default()
+        };
+
+        assert!(gen_hot_module_function_for(f, Span::call_site(), options).is_err());
+    }
+
+    #[test]
+    fn plain_function_still_panics_on_a_signature_mismatch() {
+        let f: ForeignItemFn = parse_quote!(pub fn do_stuff(arg: &str) -> u32;);
+        let (_, f, _, _) =
+            gen_hot_module_function_for(f, Span::call_site(), HotFunctionOptions::default())
+                .expect("a plain function should generate without error");
+
+        assert!(quote::quote!(#f).to_string().contains("signature mismatch"));
+    }
+
+    #[test]
+    fn optional_function_treats_a_signature_mismatch_like_a_missing_symbol() {
+        let f: ForeignItemFn = parse_quote!(pub fn do_stuff(arg: &str) -> u32;);
+        let options = HotFunctionOptions {
+            optional: true,
+            ..Default::default()
+        };
+        let (_, f, _, _) = gen_hot_module_function_for(f, Span::call_site(), options)
+            .expect("an optional function should generate without error");
+
+        // `optional` exists precisely so a bad reload doesn't panic; a signature
+        // mismatch must not reintroduce one through the back door.
+        assert!(!quote::quote!(#f).to_string().contains("panic"));
+    }
+
+    #[test]
+    fn on_missing_function_falls_back_on_a_signature_mismatch_instead_of_panicking() {
+        let f: ForeignItemFn = parse_quote!(pub fn do_stuff(arg: &str) -> u32;);
+        let options = HotFunctionOptions {
+            on_missing: Some(parse_quote!("do_stuff_fallback")),
+            ..Default::default()
+        };
+        let (_, f, _, _) = gen_hot_module_function_for(f, Span::call_site(), options)
+            .expect("an on_missing function should generate without error");
+
+        let body = quote::quote!(#f).to_string();
+        assert!(!body.contains("panic"));
+        assert!(body.contains("do_stuff_fallback"));
+    }
+}
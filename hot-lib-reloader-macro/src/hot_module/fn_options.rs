@@ -0,0 +1,151 @@
+use syn::{
+    parse::{Parse, ParseStream},
+    Attribute, Ident, LitStr, Result, Token,
+};
+
+/// One entry of a comma-separated `#[hot_function(...)]` argument list: either a
+/// bare flag (`optional`) or a `key = "value"` pair (`symbol = "do_stuff_v2"`), in
+/// the style of rhai's `parse_attr_items`.
+enum AttrItem {
+    Flag(Ident),
+    KeyValue(Ident, LitStr),
+}
+
+fn parse_attr_items(input: ParseStream) -> Result<Vec<AttrItem>> {
+    let mut items = Vec::new();
+
+    while !input.is_empty() {
+        let key: Ident = input.parse()?;
+
+        let item = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            AttrItem::KeyValue(key, input.parse()?)
+        } else {
+            AttrItem::Flag(key)
+        };
+        items.push(item);
+
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+    }
+
+    Ok(items)
+}
+
+/// Per-function options configurable via `#[hot_function(...)]`, either directly on
+/// a `fn` item or on an individual declaration inside a `#[hot_functions] extern { .. }`
+/// block.
+#[derive(Default)]
+pub(crate) struct HotFunctionOptions {
+    /// `symbol = "..."`: the exported symbol name to resolve, if it differs from
+    /// the Rust function's own name. If signature verification is also in use,
+    /// pair this with a matching `#[hot_export_signature(symbol = "...")]` on the
+    /// lib side so both sides agree on the hash symbol's name.
+    pub(crate) symbol: Option<LitStr>,
+    /// `optional`: generate a wrapper that returns `Option` (`None` when the symbol
+    /// is missing from the loaded library) instead of panicking.
+    pub(crate) optional: bool,
+    /// `on_missing = "..."`: name of a fallback function to call instead of
+    /// panicking when the symbol is missing.
+    pub(crate) on_missing: Option<LitStr>,
+}
+
+impl Parse for HotFunctionOptions {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut options = HotFunctionOptions::default();
+
+        for item in parse_attr_items(input)? {
+            match item {
+                AttrItem::Flag(ident) if ident == "optional" => options.optional = true,
+                AttrItem::KeyValue(ident, value) if ident == "symbol" => {
+                    options.symbol = Some(value)
+                }
+                AttrItem::KeyValue(ident, value) if ident == "on_missing" => {
+                    options.on_missing = Some(value)
+                }
+                AttrItem::Flag(ident) | AttrItem::KeyValue(ident, _) => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("unknown `hot_function` option `{ident}`"),
+                    ))
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl HotFunctionOptions {
+    /// Looks for a `#[hot_function(...)]` attribute among `attrs` and parses its
+    /// options, defaulting to `HotFunctionOptions::default()` when the attribute is
+    /// absent or carries no arguments (a bare `#[hot_function]`).
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        for attr in attrs {
+            if attr.path.is_ident("hot_function") {
+                return if attr.tokens.is_empty() {
+                    Ok(Self::default())
+                } else {
+                    attr.parse_args_with(HotFunctionOptions::parse)
+                };
+            }
+        }
+
+        Ok(Self::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn options_from(tokens: proc_macro2::TokenStream) -> HotFunctionOptions {
+        let func: syn::ItemFn = syn::parse2(tokens).unwrap();
+        HotFunctionOptions::from_attrs(&func.attrs).unwrap()
+    }
+
+    #[test]
+    fn bare_hot_function_attribute_has_no_options() {
+        let options = options_from(quote::quote! {
+            #[hot_function]
+            fn do_stuff() {}
+        });
+        assert!(options.symbol.is_none());
+        assert!(!options.optional);
+        assert!(options.on_missing.is_none());
+    }
+
+    #[test]
+    fn parses_symbol_and_optional() {
+        let options = options_from(quote::quote! {
+            #[hot_function(symbol = "do_stuff_v2", optional)]
+            fn do_stuff() {}
+        });
+        assert_eq!(options.symbol.map(|s| s.value()), Some("do_stuff_v2".to_string()));
+        assert!(options.optional);
+    }
+
+    #[test]
+    fn parses_on_missing() {
+        let options = options_from(quote::quote! {
+            #[hot_function(on_missing = "do_stuff_fallback")]
+            fn do_stuff() {}
+        });
+        assert_eq!(
+            options.on_missing.map(|s| s.value()),
+            Some("do_stuff_fallback".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_option_is_an_error() {
+        let func: syn::ItemFn = parse_quote! {
+            #[hot_function(bogus)]
+            fn do_stuff() {}
+        };
+        assert!(HotFunctionOptions::from_attrs(&func.attrs).is_err());
+    }
+}
@@ -0,0 +1,51 @@
+mod code_gen;
+mod fn_options;
+mod module_body;
+
+pub(crate) use module_body::HotModule;
+
+use syn::{
+    parse::{Parse, ParseStream},
+    LitStr, Result, Token,
+};
+
+/// The `(name = "...", lib_dir = "...")` arguments of `#[hot_module(...)]`.
+pub(crate) struct HotModuleAttribute {
+    pub(crate) lib_name: LitStr,
+    pub(crate) lib_dir: LitStr,
+}
+
+impl Parse for HotModuleAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut lib_name = None;
+        let mut lib_dir = None;
+
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value: LitStr = input.parse()?;
+
+            match key.to_string().as_str() {
+                "name" => lib_name = Some(value),
+                "lib_dir" => lib_dir = Some(value),
+                other => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("unknown `hot_module` attribute `{other}`"),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let lib_name = lib_name
+            .ok_or_else(|| syn::Error::new(input.span(), "missing required attribute `name`"))?;
+
+        let lib_dir = lib_dir.unwrap_or_else(|| LitStr::new("target/debug", lib_name.span()));
+
+        Ok(Self { lib_name, lib_dir })
+    }
+}
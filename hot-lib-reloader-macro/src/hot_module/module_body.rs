@@ -1,18 +1,25 @@
 use syn::{
-    spanned::Spanned, token, ForeignItemFn, Ident, Item, ItemMacro, LitStr, Macro, Result,
-    Visibility,
+    spanned::Spanned, token, ForeignItemFn, Ident, Item, ItemMacro, Macro, Result, Visibility,
 };
 
 use super::code_gen::gen_hot_module_function_for;
-use crate::util::read_unmangled_functions_from_file;
+use super::fn_options::HotFunctionOptions;
+use crate::util::{read_unmangled_functions_from_file, HotFunctionsFromFileArgs};
 
-use super::{code_gen::generate_lib_loader_items, HotModuleAttribute};
+use super::{
+    code_gen::{generate_lib_loader_items, generate_verify_symbols_items},
+    HotModuleAttribute,
+};
 
 pub(crate) struct HotModule {
     pub(crate) vis: Visibility,
     pub(crate) ident: Ident,
     pub(crate) items: Vec<Item>,
     pub(crate) attributes: Option<super::HotModuleAttribute>,
+    /// Exported symbol name of every generated wrapper function whose symbol is
+    /// required (`optional`/`on_missing` functions are left out), used to back
+    /// `verify_symbols()`.
+    pub(crate) expected_symbols: Vec<String>,
 }
 
 /// Parses something like
@@ -45,26 +52,35 @@ impl syn::parse::Parse for HotModule {
         syn::braced!(module_body_stream in stream);
 
         let mut items = Vec::new();
+        let mut expected_symbols = Vec::new();
 
         while !module_body_stream.is_empty() {
             let item = module_body_stream.parse::<syn::Item>()?;
 
             match item {
-                // parses the hot_functions_from_file!("path/to/file.rs") marker
+                // parses the hot_functions_from_file!("path/to/file.rs") marker, and
+                // its `recurse = true` form for crates that split their public API
+                // across `mod` submodules: hot_functions_from_file!("../lib/src/lib.rs", recurse = true)
                 Item::Macro(ItemMacro {
                     mac: Macro { path, tokens, .. },
                     ..
                 }) if path.is_ident("hot_functions_from_file") => {
-                    let file_name: LitStr = syn::parse(tokens.into())?;
-                    let functions = read_unmangled_functions_from_file(file_name)?;
+                    let args: HotFunctionsFromFileArgs = syn::parse(tokens.into())?;
+                    let functions =
+                        read_unmangled_functions_from_file(args.file_name, args.recurse)?;
                     for (f, span) in functions {
-                        let f = gen_hot_module_function_for(f, span)?;
+                        let (sig_const, f, symbol, is_required) =
+                            gen_hot_module_function_for(f, span, HotFunctionOptions::default())?;
+                        items.push(Item::Const(sig_const));
                         items.push(Item::Fn(f));
+                        if is_required {
+                            expected_symbols.push(symbol);
+                        }
                     }
                 }
 
                 // parses and code gens
-                // #[hot_function]
+                // #[hot_function(symbol = "...", optional, on_missing = "...")]
                 // fn do_stuff(arg: &str) -> u32 {}
                 syn::Item::Fn(func)
                     if func
@@ -73,14 +89,20 @@ impl syn::parse::Parse for HotModule {
                         .any(|attr| attr.path.is_ident("hot_function")) =>
                 {
                     let span = func.span();
+                    let options = HotFunctionOptions::from_attrs(&func.attrs)?;
                     let f = ForeignItemFn {
                         attrs: Vec::new(),
                         vis: func.vis,
                         sig: func.sig,
                         semi_token: token::Semi::default(),
                     };
-                    let f = gen_hot_module_function_for(f, span)?;
+                    let (sig_const, f, symbol, is_required) =
+                        gen_hot_module_function_for(f, span, options)?;
+                    items.push(Item::Const(sig_const));
                     items.push(Item::Fn(f));
+                    if is_required {
+                        expected_symbols.push(symbol);
+                    }
                 }
 
                 // parses and code gens
@@ -98,13 +120,21 @@ impl syn::parse::Parse for HotModule {
                         match item {
                             syn::ForeignItem::Fn(f) => {
                                 let span = f.span();
-                                let f = gen_hot_module_function_for(f, span)?;
+                                let options = HotFunctionOptions::from_attrs(&f.attrs)?;
+                                let (sig_const, f, symbol, is_required) =
+                                    gen_hot_module_function_for(f, span, options)?;
+                                items.push(Item::Const(sig_const));
                                 items.push(Item::Fn(f));
+                                if is_required {
+                                    expected_symbols.push(symbol);
+                                }
                             }
-                            _ => {
-                                eprintln!(
-                                    "[warn] hot_functions extern block includes unexpected items"
-                                );
+                            item => {
+                                return Err(syn::Error::new(
+                                    item.span(),
+                                    "`#[hot_functions]` extern blocks may only contain function \
+                                     declarations",
+                                ))
                             }
                         }
                     }
@@ -120,6 +150,7 @@ impl syn::parse::Parse for HotModule {
             vis,
             items,
             attributes: None,
+            expected_symbols,
         })
     }
 }
@@ -131,6 +162,7 @@ impl quote::ToTokens for HotModule {
             ident,
             items,
             attributes,
+            expected_symbols,
         } = self;
 
         let HotModuleAttribute { lib_name, lib_dir } = match attributes {
@@ -140,12 +172,16 @@ impl quote::ToTokens for HotModule {
 
         let lib_loader = generate_lib_loader_items(lib_dir, lib_name, tokens.span())
             .expect("error generating hot lib loader helpers");
+        let verify_symbols = generate_verify_symbols_items(expected_symbols)
+            .expect("error generating verify_symbols helpers");
 
         let module_def = quote::quote! {
             #vis mod #ident {
                 #( #items )*
 
                 #lib_loader
+
+                #verify_symbols
             }
         };
 
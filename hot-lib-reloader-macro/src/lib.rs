@@ -0,0 +1,141 @@
+//! Proc macro implementations backing the `hot-lib-reloader` crate.
+//!
+//! The public entry point is `#[hot_module(...)]`, which rewrites a `mod` block
+//! whose functions are implemented in a dynamic library so that calls go through
+//! generated wrappers that load the library lazily and reload it on changes.
+
+mod hot_module;
+mod sig_hash;
+mod util;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, ItemFn, LitStr, Token,
+};
+
+use hot_module::{HotModule, HotModuleAttribute};
+use sig_hash::{hash_signature, sig_hash_symbol_name};
+
+/// Options accepted by `#[hot_export_signature(...)]`.
+#[derive(Default)]
+struct HotExportSignatureOptions {
+    /// `symbol = "..."`: export the signature hash under this name instead of
+    /// the function's own name. Needed when the corresponding `#[hot_module]`
+    /// wrapper was generated with `#[hot_function(symbol = "...")]`, since the
+    /// wrapper resolves the hash symbol under the *renamed* name.
+    symbol: Option<LitStr>,
+}
+
+impl Parse for HotExportSignatureOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = HotExportSignatureOptions::default();
+
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            if key == "symbol" {
+                input.parse::<Token![=]>()?;
+                options.symbol = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("unknown `hot_export_signature` option `{key}`"),
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Turns a `mod { .. }` block into a hot-reloadable module. See the crate-level
+/// docs of `hot-lib-reloader` for the full usage example.
+#[proc_macro_attribute]
+pub fn hot_module(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let attributes = parse_macro_input!(attr as HotModuleAttribute);
+    let mut hot_module = parse_macro_input!(item as HotModule);
+    hot_module.attributes = Some(attributes);
+
+    quote::quote!(#hot_module).into()
+}
+
+/// Place on a function in the *reloaded* lib crate to let `#[hot_module]` wrappers
+/// verify, at runtime, that this function's signature still matches what they were
+/// generated against.
+///
+/// Exports an additional `#[no_mangle]` symbol (`__hot_sig_<fn_name>`) returning a
+/// hash of the function's signature, computed the same way `#[hot_module]` hashes
+/// the signature it generates a wrapper for. A generated wrapper that can't find
+/// this symbol simply skips the check, so adding it is optional and backwards
+/// compatible.
+///
+/// If the corresponding `#[hot_module]` wrapper uses `#[hot_function(symbol =
+/// "...")]` to resolve this function under a different name, pass the same name
+/// here via `#[hot_export_signature(symbol = "...")]` so both sides compute the
+/// hash symbol for the same name; otherwise the wrapper looks for the hash under
+/// the renamed symbol, never finds it, and silently skips the check.
+#[proc_macro_attribute]
+pub fn hot_export_signature(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options = if attr.is_empty() {
+        HotExportSignatureOptions::default()
+    } else {
+        parse_macro_input!(attr as HotExportSignatureOptions)
+    };
+    let item_fn = parse_macro_input!(item as ItemFn);
+    let sig_hash = hash_signature(&item_fn.sig);
+    let exported_ident = match &options.symbol {
+        Some(lit) => match util::parse_ident_literal(lit) {
+            Ok(ident) => ident,
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => item_fn.sig.ident.clone(),
+    };
+    let sig_hash_fn = syn::Ident::new(
+        &sig_hash_symbol_name(&exported_ident.to_string()),
+        item_fn.sig.ident.span(),
+    );
+
+    quote! {
+        #item_fn
+
+        #[no_mangle]
+        #[doc(hidden)]
+        pub extern "C" fn #sig_hash_fn() -> u64 {
+            #sig_hash
+        }
+    }
+    .into()
+}
+
+// `hot_module`/`hot_export_signature` themselves take `proc_macro::TokenStream`,
+// which can only be constructed while the compiler is actually expanding a
+// macro, so only `HotExportSignatureOptions`'s `syn` parsing (built on
+// `proc_macro2`) is unit-testable here.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_attribute_has_no_symbol_override() {
+        let options: HotExportSignatureOptions = syn::parse2(quote!()).unwrap();
+        assert!(options.symbol.is_none());
+    }
+
+    #[test]
+    fn parses_symbol_override() {
+        let options: HotExportSignatureOptions =
+            syn::parse2(quote!(symbol = "do_stuff_v2")).unwrap();
+        assert_eq!(options.symbol.map(|s| s.value()), Some("do_stuff_v2".to_string()));
+    }
+
+    #[test]
+    fn unknown_option_is_an_error() {
+        let result: syn::Result<HotExportSignatureOptions> = syn::parse2(quote!(bogus));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use quote::ToTokens;
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+use syn::{FnArg, Signature};
+
+/// Hashes a function's argument types and return type so that a generated
+/// wrapper can detect, at runtime, whether the symbol it resolves from a reloaded
+/// library still matches the signature it was generated against.
+///
+/// Used on both sides: `#[hot_module]` embeds the hash of the signature it
+/// generated a wrapper for, and `#[hot_export_signature]` embeds the hash of the
+/// signature it sees in the reloaded lib crate, computed the exact same way so the
+/// two can be compared at runtime. Deliberately excludes the fn's own identifier:
+/// `#[hot_function(symbol = "...")]` lets the wrapper's local name differ from the
+/// lib-side export's Rust identifier, so hashing the name would make the two
+/// sides' hashes permanently disagree whenever that renaming is used.
+pub(crate) fn hash_signature(sig: &Signature) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for input in &sig.inputs {
+        let ty = match input {
+            FnArg::Typed(pat_type) => pat_type.ty.to_token_stream().to_string(),
+            FnArg::Receiver(receiver) => receiver.to_token_stream().to_string(),
+        };
+        ty.hash(&mut hasher);
+    }
+    sig.output.to_token_stream().to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Name of the symbol a reloaded lib exports to let wrappers verify `fn_name`'s
+/// signature hasn't drifted since the `#[hot_module]` was generated, e.g.
+/// `__hot_sig_do_stuff`.
+///
+/// Takes the exported name as a plain `&str` rather than a `syn::Ident`: on the
+/// wrapper side (`code_gen.rs`) this is only ever used to build the byte-string
+/// symbol name passed to `LibReloader::get_symbol`, not a real Rust
+/// identifier, so it shouldn't require `fn_name` to parse as one.
+pub(crate) fn sig_hash_symbol_name(fn_name: &str) -> String {
+    format!("__hot_sig_{fn_name}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn same_signature_hashes_the_same() {
+        let a: Signature = parse_quote!(fn do_stuff(arg: &str) -> u32);
+        let b: Signature = parse_quote!(fn do_stuff(arg: &str) -> u32);
+        assert_eq!(hash_signature(&a), hash_signature(&b));
+    }
+
+    #[test]
+    fn differing_fn_name_does_not_change_the_hash() {
+        // A renamed lib-side export (`#[hot_function(symbol = "...")]`) must
+        // still hash the same as the wrapper's own name, or the two sides could
+        // never agree.
+        let a: Signature = parse_quote!(fn do_stuff(arg: &str) -> u32);
+        let b: Signature = parse_quote!(fn do_stuff_v2(arg: &str) -> u32);
+        assert_eq!(hash_signature(&a), hash_signature(&b));
+    }
+
+    #[test]
+    fn differing_arg_type_changes_the_hash() {
+        let a: Signature = parse_quote!(fn do_stuff(arg: &str) -> u32);
+        let b: Signature = parse_quote!(fn do_stuff(arg: u32) -> u32);
+        assert_ne!(hash_signature(&a), hash_signature(&b));
+    }
+
+    #[test]
+    fn differing_return_type_changes_the_hash() {
+        let a: Signature = parse_quote!(fn do_stuff(arg: &str) -> u32);
+        let b: Signature = parse_quote!(fn do_stuff(arg: &str) -> u64);
+        assert_ne!(hash_signature(&a), hash_signature(&b));
+    }
+
+    #[test]
+    fn sig_hash_symbol_name_is_mangled_with_prefix() {
+        assert_eq!(sig_hash_symbol_name("do_stuff"), "__hot_sig_do_stuff");
+    }
+}
@@ -0,0 +1,365 @@
+use std::{fs, path::Path};
+
+use proc_macro2::Span;
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Attribute, ForeignItemFn, Item, LitBool, LitStr, Meta, NestedMeta, Result, Token,
+};
+
+/// Function names we never want to pick up even if they happen to be `pub` and
+/// `#[no_mangle]`, mirroring the small exclusion list cbindgen's own parser keeps
+/// for items that aren't really part of a crate's public API.
+const EXCLUDED_FN_NAMES: &[&str] = &["main", "_start"];
+
+/// Parses a user-supplied string literal (e.g. an `on_missing = "..."` value) as a
+/// Rust identifier, so an attribute value that isn't a valid identifier surfaces as
+/// a span-pointed `syn::Error` instead of panicking inside `format_ident!`/`Ident::new`.
+pub(crate) fn parse_ident_literal(lit: &LitStr) -> Result<syn::Ident> {
+    syn::parse_str(&lit.value()).map_err(|_| {
+        syn::Error::new(lit.span(), format!("`{}` is not a valid identifier", lit.value()))
+    })
+}
+
+/// Arguments to `hot_functions_from_file!`, e.g.
+/// `hot_functions_from_file!("../lib/src/lib.rs", recurse = true)`.
+pub(crate) struct HotFunctionsFromFileArgs {
+    pub(crate) file_name: LitStr,
+    pub(crate) recurse: bool,
+}
+
+impl Parse for HotFunctionsFromFileArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let file_name: LitStr = input.parse()?;
+        let mut recurse = false;
+
+        if input.parse::<Option<Token![,]>>()?.is_some() {
+            let key: syn::Ident = input.parse()?;
+            if key != "recurse" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("unknown `hot_functions_from_file!` argument `{key}`"),
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            recurse = input.parse::<LitBool>()?.value;
+        }
+
+        Ok(Self { file_name, recurse })
+    }
+}
+
+/// Reads a source file and collects the signatures of every `#[no_mangle] pub`
+/// function it declares (whatever its ABI), so `hot_functions_from_file!("path/to/lib.rs")` can generate
+/// wrappers without the user having to repeat each signature by hand.
+///
+/// When `recurse` is `true`, `mod foo;` declarations are followed to `foo.rs` /
+/// `foo/mod.rs` (resolved relative to `file_name`'s directory) and `mod foo { .. }`
+/// blocks are descended into, so pointing this at a crate's `lib.rs` picks up its
+/// whole public surface. Items gated by a `#[cfg(not(..))]`, `#[cfg(any(..))]` or
+/// `#[cfg(all(..))]` that can be resolved structurally, or that appear in
+/// `EXCLUDED_FN_NAMES`, are filtered out rather than blindly emitted. Leaf
+/// predicates like `cfg(test)` or `cfg(feature = "...")` describe the scanned
+/// crate's own build, which we have no reliable way to evaluate from here, so
+/// those are always kept.
+pub(crate) fn read_unmangled_functions_from_file(
+    file_name: LitStr,
+    recurse: bool,
+) -> Result<Vec<(ForeignItemFn, Span)>> {
+    let path = Path::new(&file_name.value()).to_path_buf();
+    let dir = path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+    let mut functions = Vec::new();
+    read_unmangled_functions_from_path(&path, &dir, file_name.span(), recurse, &mut functions)?;
+    Ok(functions)
+}
+
+/// `dir` is the directory any `mod foo;` declaration found in `path` should
+/// resolve its own nested `mod bar;` declarations under (i.e. `dir.join("foo")`),
+/// which for a non-root file is *not* necessarily `path`'s own parent directory:
+/// `src/foo.rs`'s parent is `src/`, but a `mod bar;` inside it resolves to
+/// `src/foo/bar.rs`.
+fn read_unmangled_functions_from_path(
+    path: &Path,
+    dir: &Path,
+    err_span: Span,
+    recurse: bool,
+    functions: &mut Vec<(ForeignItemFn, Span)>,
+) -> Result<()> {
+    let content = fs::read_to_string(path).map_err(|err| {
+        syn::Error::new(err_span, format!("could not read file `{}`: {err}", path.display()))
+    })?;
+
+    let file = syn::parse_file(&content).map_err(|err| {
+        syn::Error::new(err_span, format!("could not parse file `{}`: {err}", path.display()))
+    })?;
+
+    collect_unmangled_functions(file.items, dir, err_span, recurse, functions)
+}
+
+fn collect_unmangled_functions(
+    items: Vec<Item>,
+    dir: &Path,
+    err_span: Span,
+    recurse: bool,
+    functions: &mut Vec<(ForeignItemFn, Span)>,
+) -> Result<()> {
+    for item in items {
+        match item {
+            Item::Fn(item_fn) if !is_cfg_excluded(&item_fn.attrs) => {
+                let is_exported = item_fn
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path.is_ident("no_mangle"))
+                    && matches!(item_fn.vis, syn::Visibility::Public(_))
+                    && !EXCLUDED_FN_NAMES.contains(&item_fn.sig.ident.to_string().as_str());
+
+                if is_exported {
+                    let span = item_fn.span();
+                    functions.push((
+                        ForeignItemFn {
+                            attrs: Vec::new(),
+                            vis: item_fn.vis,
+                            sig: item_fn.sig,
+                            semi_token: Default::default(),
+                        },
+                        span,
+                    ));
+                }
+            }
+
+            Item::Mod(item_mod) if recurse && !is_cfg_excluded(&item_mod.attrs) => {
+                let mod_name = item_mod.ident.to_string();
+                // Nested `mod` declarations found under `foo`, whether `foo` is
+                // this inline block or the file resolved below, live under
+                // `dir/foo/`, regardless of whether `foo` itself is `foo.rs` or
+                // `foo/mod.rs`.
+                let mod_dir = dir.join(&mod_name);
+
+                match item_mod.content {
+                    // `mod foo { .. }`: descend into the inline items directly.
+                    Some((_, items)) => {
+                        collect_unmangled_functions(items, &mod_dir, err_span, recurse, functions)?;
+                    }
+                    // `mod foo;`: resolve to `foo.rs` or `foo/mod.rs`.
+                    None => {
+                        let candidate_file = dir.join(format!("{mod_name}.rs"));
+                        let candidate_dir = mod_dir.join("mod.rs");
+
+                        let resolved = if candidate_file.is_file() {
+                            Some(candidate_file)
+                        } else if candidate_dir.is_file() {
+                            Some(candidate_dir)
+                        } else {
+                            None
+                        };
+
+                        if let Some(mod_path) = resolved {
+                            read_unmangled_functions_from_path(
+                                &mod_path, &mod_dir, err_span, recurse, functions,
+                            )?;
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether this item is gated by a `#[cfg(..)]` that resolves structurally to
+/// `false` for the scanned crate's build (see `resolve_cfg_list`).
+/// Unrecognized predicates are kept rather than excluded, so we only ever filter
+/// out items we're confident don't apply, matching the macro's existing
+/// fail-open behaviour for anything it doesn't understand.
+fn is_cfg_excluded(attrs: &[Attribute]) -> bool {
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("cfg")) {
+        let Ok(Meta::List(list)) = attr.parse_meta() else {
+            continue;
+        };
+
+        // `list` here is the outer `cfg(..)` itself (its `path` is literally
+        // `cfg`), so dispatch on the predicate(s) nested inside it, not on
+        // `list` directly. Only a predicate that resolves to a definite `false`
+        // excludes the item; anything unresolvable keeps it.
+        if list.nested.iter().any(|nested| resolve_nested_cfg(nested) == Some(false)) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Evaluates a `#[cfg(..)]` predicate against the *scanned file*'s build, not
+/// this proc-macro crate's own. We only have enough information to resolve the
+/// `not`/`any`/`all` combinators structurally; leaf predicates like `test`,
+/// `feature = "..."`, or `target_os = "..."` describe the configuration of the
+/// crate being scanned (a separate crate read from disk via `fs::read_to_string`,
+/// not the crate this proc-macro is compiled as part of), so we can't evaluate
+/// them reliably and keep the item rather than silently mis-filtering it.
+///
+/// Returns `Some(true)`/`Some(false)` when the predicate is fully resolvable from
+/// `not`/`any`/`all` structure alone, or `None` when it bottoms out in at least
+/// one leaf we can't evaluate either way. Crucially, `None` propagates through
+/// `any`/`all`/`not` rather than being coerced to a stand-in `true`/`false`
+/// partway through: an `any`/`all` of only unresolvable leaves is itself `None`
+/// (not a fail-open `Some(true)`), so negating it via an enclosing `not(..)`
+/// still correctly yields `None` — the unresolvable case — instead of flipping
+/// a fail-open sentinel into a confident `false` that wrongly excludes the item
+/// (e.g. `cfg(not(any(feature = "a", feature = "b")))`).
+fn resolve_cfg_list(list: &syn::MetaList) -> Option<bool> {
+    let ident = list.path.get_ident().map(|i| i.to_string());
+    match ident.as_deref() {
+        Some("not") => list.nested.iter().next().and_then(resolve_nested_cfg).map(|holds| !holds),
+        Some("any") => {
+            let mut saw_unresolvable = false;
+            for nested in &list.nested {
+                match resolve_nested_cfg(nested) {
+                    Some(true) => return Some(true),
+                    Some(false) => {}
+                    None => saw_unresolvable = true,
+                }
+            }
+            (!saw_unresolvable).then_some(false)
+        }
+        Some("all") => {
+            let mut saw_unresolvable = false;
+            for nested in &list.nested {
+                match resolve_nested_cfg(nested) {
+                    Some(false) => return Some(false),
+                    Some(true) => {}
+                    None => saw_unresolvable = true,
+                }
+            }
+            (!saw_unresolvable).then_some(true)
+        }
+        _ => None,
+    }
+}
+
+fn resolve_nested_cfg(nested: &NestedMeta) -> Option<bool> {
+    match nested {
+        NestedMeta::Meta(Meta::List(list)) => resolve_cfg_list(list),
+        // Leaf predicates (`test`, `feature = "..."`, `target_os = "..."`, ...)
+        // can't be evaluated reliably from here, see `resolve_cfg_list`.
+        NestedMeta::Meta(Meta::Path(_)) => None,
+        NestedMeta::Meta(Meta::NameValue(_)) => None,
+        NestedMeta::Lit(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attrs_of(tokens: proc_macro2::TokenStream) -> Vec<Attribute> {
+        let item_fn: syn::ItemFn = syn::parse2(tokens).unwrap();
+        item_fn.attrs
+    }
+
+    #[test]
+    fn cfg_test_is_kept_since_it_describes_the_scanned_crate_not_this_one() {
+        let attrs = attrs_of(quote::quote! {
+            #[cfg(test)]
+            fn foo() {}
+        });
+        assert!(!is_cfg_excluded(&attrs));
+    }
+
+    #[test]
+    fn cfg_feature_is_kept_regardless_of_this_crate_s_own_features() {
+        let attrs = attrs_of(quote::quote! {
+            #[cfg(feature = "some-feature-this-crate-does-not-have")]
+            fn foo() {}
+        });
+        assert!(!is_cfg_excluded(&attrs));
+    }
+
+    #[test]
+    fn cfg_not_of_an_unresolvable_leaf_is_kept_on_every_platform() {
+        let attrs = attrs_of(quote::quote! {
+            #[cfg(not(unix))]
+            fn foo() {}
+        });
+        assert!(!is_cfg_excluded(&attrs));
+
+        let attrs = attrs_of(quote::quote! {
+            #[cfg(not(feature = "some-feature-this-crate-does-not-have"))]
+            fn foo() {}
+        });
+        assert!(!is_cfg_excluded(&attrs));
+    }
+
+    #[test]
+    fn cfg_not_any_or_all_of_unresolvable_leaves_is_kept_on_every_platform() {
+        // `any(..)`/`all(..)` of only-unresolvable leaves must itself stay
+        // unresolvable (not a fail-open `true`), or negating it via `not(..)`
+        // would wrongly flip that sentinel into a confident `false` and always
+        // exclude the item, e.g. a `#[cfg(not(any(feature = "a", feature =
+        // "b")))]`-gated fn that's actually compiled in on the default build.
+        let attrs = attrs_of(quote::quote! {
+            #[cfg(not(any(feature = "a", feature = "b")))]
+            fn foo() {}
+        });
+        assert!(!is_cfg_excluded(&attrs));
+
+        let attrs = attrs_of(quote::quote! {
+            #[cfg(not(all(feature = "a", feature = "b")))]
+            fn foo() {}
+        });
+        assert!(!is_cfg_excluded(&attrs));
+    }
+
+    #[test]
+    fn no_cfg_attribute_is_never_excluded() {
+        let attrs = attrs_of(quote::quote! {
+            fn foo() {}
+        });
+        assert!(!is_cfg_excluded(&attrs));
+    }
+
+    #[test]
+    fn hot_functions_from_file_args_parse_recurse_flag() {
+        let args: HotFunctionsFromFileArgs =
+            syn::parse2(quote::quote!("../lib/src/lib.rs", recurse = true)).unwrap();
+        assert_eq!(args.file_name.value(), "../lib/src/lib.rs");
+        assert!(args.recurse);
+    }
+
+    #[test]
+    fn hot_functions_from_file_args_default_to_no_recurse() {
+        let args: HotFunctionsFromFileArgs =
+            syn::parse2(quote::quote!("../lib/src/lib.rs")).unwrap();
+        assert!(!args.recurse);
+    }
+
+    #[test]
+    fn recurse_resolves_mod_declarations_nested_under_a_file_mod() {
+        // `lib.rs` -> `mod foo;` (`foo.rs`) -> `mod bar;`, expecting `foo/bar.rs`,
+        // the standard Rust 2018+ layout.
+        let base = std::env::temp_dir().join(format!(
+            "hot_lib_reloader_macro_test_{}_recurse_nested_mod",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("foo")).unwrap();
+        fs::write(base.join("lib.rs"), "mod foo;\n").unwrap();
+        fs::write(base.join("foo.rs"), "mod bar;\n").unwrap();
+        fs::write(
+            base.join("foo").join("bar.rs"),
+            "#[no_mangle]\npub extern \"C\" fn baz() {}\n",
+        )
+        .unwrap();
+
+        let file_name = LitStr::new(base.join("lib.rs").to_str().unwrap(), Span::call_site());
+        let functions = read_unmangled_functions_from_file(file_name, true);
+
+        fs::remove_dir_all(&base).unwrap();
+
+        let functions = functions.unwrap();
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions[0].0.sig.ident, "baz");
+    }
+}